@@ -0,0 +1,321 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use anyhow::Context;
+use rusqlite::{params_from_iter, Connection, OptionalExtension, Row};
+use sha1::Sha1;
+
+use crate::{
+    cookie::{MozCookie, SameSite},
+    keyring,
+};
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+/// Columns read from Chromium's `cookies` table for every cookie.
+pub static COLUMNS: &str = "host_key, name, value, encrypted_value, path, expires_utc, is_secure, \
+    is_httponly, samesite, creation_utc, last_access_utc";
+
+/// Microseconds between 1601-01-01, where Chromium's clock starts, and the Unix epoch.
+const WINDOWS_EPOCH_OFFSET: i64 = 11_644_473_600_000_000;
+
+/// The password Chromium on Linux encrypts `v10` values with when no keyring is in use.
+const V10_PASSWORD: &[u8] = b"peanuts";
+
+/// Databases from this version on prefix each plaintext with the SHA-256 of its host.
+const HOST_HASH_VERSION: i64 = 24;
+
+/// Tests whether the database holds Chromium's `cookies` table rather than `moz_cookies`.
+pub fn is_chromium(connection: &Connection) -> rusqlite::Result<bool> {
+    connection.query_row(
+        "select count(*) from sqlite_master where type = 'table' and name = 'cookies'",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Reads and decrypts the cookies selected by `filter`, a condition on `host_key` with `params`
+/// bound to its placeholders.
+///
+/// `v11` values are decrypted with `keyring_password` when given, and otherwise with the
+/// password the Secret Service holds under `safe_storage`, which is only asked for once such a
+/// value turns up. Values that still fail to decrypt, say after the keyring was reset, are
+/// left out with a warning rather than spoiling the rest.
+pub fn read(
+    connection: &Connection,
+    filter: &str,
+    params: &[String],
+    safe_storage: &str,
+    keyring_password: Option<&str>,
+) -> anyhow::Result<Vec<MozCookie>> {
+    let version = meta_version(connection)?;
+    let mut keys = Keys {
+        safe_storage,
+        v10: derive_key(V10_PASSWORD),
+        v11: keyring_password.map(|password| derive_key(password.as_bytes())),
+    };
+
+    let query = format!("select {} from cookies where {}", COLUMNS, filter);
+    let mut s = connection.prepare(&query)?;
+    let rows = s
+        .query_map(params_from_iter(params), RawCookie::from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut cookies = Vec::new();
+    let mut skipped = 0;
+    for row in rows {
+        let value = if row.encrypted_value.is_empty() {
+            row.value
+        } else {
+            let encrypted = &row.encrypted_value;
+            let (prefix, ciphertext) = encrypted.split_at(3.min(encrypted.len()));
+            let key = keys
+                .key(prefix)
+                .with_context(|| format!("cannot decrypt cookie {} for {}", row.name, row.host))?;
+
+            match key.and_then(|key| decrypt(ciphertext, &key, version).ok()) {
+                Some(value) => value,
+                None => {
+                    skipped += 1;
+                    continue;
+                }
+            }
+        };
+
+        cookies.push(MozCookie {
+            value,
+            expiry: match row.expires_utc {
+                0 => 0,
+                expires => (expires - WINDOWS_EPOCH_OFFSET) / 1_000_000,
+            },
+            creation_time: row.creation_utc - WINDOWS_EPOCH_OFFSET,
+            last_accessed: row.last_access_utc - WINDOWS_EPOCH_OFFSET,
+            host: row.host,
+            path: row.path,
+            name: row.name,
+            is_secure: row.is_secure,
+            is_http_only: row.is_http_only,
+            same_site: SameSite::from_moz(row.same_site),
+            origin_attributes: String::new(),
+            is_partitioned: false,
+        });
+    }
+
+    if skipped > 0 {
+        eprintln!(
+            "warning: skipped {} cookie{} that could not be decrypted",
+            skipped,
+            if skipped == 1 { "" } else { "s" }
+        );
+    }
+
+    Ok(cookies)
+}
+
+/// A row of the `cookies` table, before its value is decrypted.
+struct RawCookie {
+    host: String,
+    name: String,
+    value: String,
+    encrypted_value: Vec<u8>,
+    path: String,
+    expires_utc: i64,
+    is_secure: bool,
+    is_http_only: bool,
+    same_site: i32,
+    creation_utc: i64,
+    last_access_utc: i64,
+}
+
+impl RawCookie {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(RawCookie {
+            host: row.get("host_key")?,
+            name: row.get("name")?,
+            value: row.get("value")?,
+            encrypted_value: row.get("encrypted_value")?,
+            path: row.get("path")?,
+            expires_utc: row.get("expires_utc")?,
+            is_secure: row.get("is_secure")?,
+            is_http_only: row.get("is_httponly")?,
+            same_site: row.get("samesite")?,
+            creation_utc: row.get("creation_utc")?,
+            last_access_utc: row.get("last_access_utc")?,
+        })
+    }
+}
+
+fn meta_version(connection: &Connection) -> rusqlite::Result<i64> {
+    let version: Option<String> = connection
+        .query_row("select value from meta where key = 'version'", [], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    Ok(version.and_then(|v| v.parse().ok()).unwrap_or_default())
+}
+
+/// Derives an AES-128 key from a Safe Storage password the way Chromium on Linux does.
+fn derive_key(password: &[u8]) -> [u8; 16] {
+    let mut key = [0; 16];
+    pbkdf2::pbkdf2_hmac::<Sha1>(password, b"saltysalt", 1, &mut key);
+    key
+}
+
+struct Keys<'a> {
+    safe_storage: &'a str,
+    v10: [u8; 16],
+    v11: Option<[u8; 16]>,
+}
+
+impl Keys<'_> {
+    /// Picks the key for a value's version prefix, or `None` for a scheme we don't know. Fails
+    /// only when a `v11` key is needed and no password can be had.
+    fn key(&mut self, prefix: &[u8]) -> anyhow::Result<Option<[u8; 16]>> {
+        match prefix {
+            b"v10" => Ok(Some(self.v10)),
+            b"v11" => match self.v11 {
+                Some(key) => Ok(Some(key)),
+                None => {
+                    let password = keyring::password(self.safe_storage)
+                        .context("v11 cookies need a keyring password")?;
+                    Ok(Some(*self.v11.insert(derive_key(&password))))
+                }
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Decrypts a value with its version prefix removed.
+fn decrypt(ciphertext: &[u8], key: &[u8; 16], version: i64) -> anyhow::Result<String> {
+    let mut buf = ciphertext.to_vec();
+    let plaintext = Aes128CbcDec::new(key.into(), &[b' '; 16].into())
+        .decrypt_padded_mut::<Pkcs7>(&mut buf)
+        .map_err(|_| anyhow::anyhow!("bad padding"))?;
+
+    let plaintext = if version >= HOST_HASH_VERSION {
+        plaintext
+            .get(32..)
+            .ok_or_else(|| anyhow::anyhow!("value too short"))?
+    } else {
+        plaintext
+    };
+
+    Ok(String::from_utf8(plaintext.to_vec())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit};
+    use rusqlite::Connection;
+
+    use crate::cookie::SameSite;
+
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        encrypt_with(b"v10", b"peanuts", plaintext)
+    }
+
+    fn encrypt_with(prefix: &[u8], password: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let key = super::derive_key(password);
+        let mut buf = plaintext.to_vec();
+        buf.resize(plaintext.len() + 16, 0);
+        let ciphertext = cbc::Encryptor::<aes::Aes128>::new(&key.into(), &[b' '; 16].into())
+            .encrypt_padded_mut::<Pkcs7>(&mut buf, plaintext.len())
+            .unwrap();
+        [prefix, ciphertext].concat()
+    }
+
+    #[test]
+    fn decrypts_v10_values() {
+        let key = super::derive_key(b"peanuts");
+        assert_eq!(
+            key,
+            [
+                0xfd, 0x62, 0x1f, 0xe5, 0xa2, 0xb4, 0x02, 0x53, 0x9d, 0xfa, 0x14, 0x7c, 0xa9, 0x27,
+                0x27, 0x78,
+            ]
+        );
+        assert_eq!(
+            super::decrypt(&encrypt(b"hello")[3..], &key, 18).unwrap(),
+            "hello"
+        );
+
+        let hashed = [[7; 32].as_slice(), b"hello"].concat();
+        assert_eq!(
+            super::decrypt(&encrypt(&hashed)[3..], &key, 24).unwrap(),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn decrypts_v11_values_with_given_password() {
+        let mut keys = super::Keys {
+            safe_storage: "Chromium Safe Storage",
+            v10: super::derive_key(b"peanuts"),
+            v11: Some(super::derive_key(b"hunter2")),
+        };
+
+        let encrypted = encrypt_with(b"v11", b"hunter2", b"hello");
+        let key = keys.key(b"v11").unwrap().unwrap();
+        assert_eq!(super::decrypt(&encrypted[3..], &key, 18).unwrap(), "hello");
+
+        let key = keys.key(b"v10").unwrap().unwrap();
+        assert_eq!(
+            super::decrypt(&encrypt(b"hello")[3..], &key, 18).unwrap(),
+            "hello"
+        );
+        assert_eq!(keys.key(b"v99").unwrap(), None);
+    }
+
+    #[test]
+    fn reads_cookies_table() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "create table meta (key text, value text);
+                insert into meta values ('version', '21');
+                create table cookies (creation_utc integer, host_key text, name text, value text,
+                    encrypted_value blob, path text, expires_utc integer, is_secure integer,
+                    is_httponly integer, last_access_utc integer, samesite integer);",
+            )
+            .unwrap();
+        connection
+            .execute(
+                "insert into cookies values
+                    (13245000000000000, '.foo.com', 'a', '', ?, '/', 13345000000000000, 1, 1,
+                    13246000000000000, 1),
+                    (13245000000000000, 'foo.com', 'b', 'plain', x'', '/', 0, 0, 0,
+                    13246000000000000, -1),
+                    (13245000000000000, 'foo.com', 'c', '', ?, '/', 0, 0, 0,
+                    13246000000000000, -1),
+                    (13245000000000000, 'foo.com', 'd', '', ?, '/', 0, 0, 0,
+                    13246000000000000, -1)",
+                [
+                    encrypt(b"secret"),
+                    encrypt_with(b"v10", b"stale", b"secret"),
+                    encrypt_with(b"v99", b"peanuts", b"secret"),
+                ],
+            )
+            .unwrap();
+
+        assert!(super::is_chromium(&connection).unwrap());
+        let cookies = super::read(
+            &connection,
+            "host_key like ?",
+            &["%foo.com".into()],
+            "",
+            None,
+        )
+        .unwrap();
+        let summary: Vec<_> = cookies
+            .iter()
+            .map(|c| (c.host.as_str(), c.value.as_str(), c.expiry, c.same_site))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (".foo.com", "secret", 1700526400, SameSite::Lax),
+                ("foo.com", "plain", 0, SameSite::Unset),
+            ]
+        );
+        assert_eq!(cookies[0].creation_time, 1600526400000000);
+    }
+}
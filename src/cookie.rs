@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{Connection, Row};
+use serde::Serialize;
+
+/// Columns read from `moz_cookies` for every cookie, along with the value that stands in for
+/// those older Firefox versions and other browsers sharing the schema leave out.
+static COLUMNS: &[(&str, Option<&str>)] = &[
+    ("name", None),
+    ("value", None),
+    ("host", None),
+    ("path", None),
+    ("expiry", None),
+    ("isSecure", None),
+    ("isHttpOnly", Some("0")),
+    ("sameSite", Some("-1")),
+    ("creationTime", Some("0")),
+    ("lastAccessed", Some("0")),
+    ("originAttributes", Some("''")),
+    ("isPartitionedAttributeSet", Some("0")),
+];
+
+/// Builds the select list for the `moz_cookies` table in `connection`, filling in columns its
+/// schema lacks and the nulls libsoup leaves in those it has.
+pub fn columns(connection: &Connection) -> rusqlite::Result<String> {
+    let mut s = connection.prepare("select name from pragma_table_info('moz_cookies')")?;
+    let present = s
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    let columns: Vec<_> = COLUMNS
+        .iter()
+        .map(|&(column, default)| match default {
+            Some(default) if present.contains(column) => {
+                format!("coalesce({}, {}) as {}", column, default, column)
+            }
+            Some(default) => format!("{} as {}", default, column),
+            None => column.to_owned(),
+        })
+        .collect();
+    Ok(columns.join(", "))
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MozCookie {
+    pub host: String,
+    pub path: String,
+    pub name: String,
+    pub value: String,
+    /// Seconds since the Unix epoch, or 0 for a session cookie.
+    pub expiry: i64,
+    #[serde(rename = "secure")]
+    pub is_secure: bool,
+    #[serde(rename = "httpOnly")]
+    pub is_http_only: bool,
+    pub same_site: SameSite,
+    /// Microseconds since the Unix epoch.
+    pub creation_time: i64,
+    /// Microseconds since the Unix epoch.
+    pub last_accessed: i64,
+    pub origin_attributes: String,
+    /// Whether the site set the cookie with the `Partitioned` attribute. Firefox also files
+    /// third-party cookies it partitions on its own under a `partitionKey` origin attribute, so
+    /// that alone says nothing.
+    #[serde(rename = "partitioned")]
+    pub is_partitioned: bool,
+}
+
+impl MozCookie {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(MozCookie {
+            host: row.get("host")?,
+            path: row.get("path")?,
+            name: row.get("name")?,
+            value: row.get("value")?,
+            expiry: row.get("expiry")?,
+            is_secure: row.get("isSecure")?,
+            is_http_only: row.get("isHttpOnly")?,
+            same_site: SameSite::from_moz(row.get("sameSite")?),
+            creation_time: row.get("creationTime")?,
+            last_accessed: row.get("lastAccessed")?,
+            origin_attributes: row.get("originAttributes")?,
+            is_partitioned: row.get("isPartitionedAttributeSet")?,
+        })
+    }
+
+    /// The expiry as a point in time, if the cookie has one chrono can represent.
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        if self.is_session() {
+            return None;
+        }
+        Utc.timestamp_opt(self.expiry, 0).single()
+    }
+
+    /// Firefox never writes session cookies to disk, but imported cookies may be session cookies.
+    pub fn is_session(&self) -> bool {
+        self.expiry == 0
+    }
+
+    /// The `SameSite` attribute to hand the cookie to a browser with. Browsers reject
+    /// `SameSite=None` on cookies that are not `Secure`, and older Firefox databases store the
+    /// same 0 for an explicit `None` as for no policy at all, so such cookies go without.
+    pub fn same_site_attribute(&self) -> Option<&'static str> {
+        match self.same_site {
+            SameSite::None if !self.is_secure => None,
+            same_site => same_site.attribute(),
+        }
+    }
+
+    /// Domain cookies are stored with a leading dot; host-only cookies are not.
+    pub fn include_subdomains(&self) -> bool {
+        self.host.starts_with('.')
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SameSite {
+    None,
+    Lax,
+    Strict,
+    /// Set by newer versions of Firefox for cookies that never named a policy.
+    Unset,
+}
+
+impl SameSite {
+    /// Maps the `nsICookie` constants stored in `moz_cookies.sameSite`. Chromium's `samesite`
+    /// column uses the same numbers, with -1 for unspecified.
+    pub fn from_moz(value: i32) -> Self {
+        match value {
+            0 => SameSite::None,
+            1 => SameSite::Lax,
+            2 => SameSite::Strict,
+            _ => SameSite::Unset,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SameSite::None => "none",
+            SameSite::Lax => "lax",
+            SameSite::Strict => "strict",
+            SameSite::Unset => "unset",
+        }
+    }
+
+    /// The value of a `SameSite` attribute naming this policy, or `None` when there is none.
+    pub fn attribute(self) -> Option<&'static str> {
+        match self {
+            SameSite::None => Some("None"),
+            SameSite::Lax => Some("Lax"),
+            SameSite::Strict => Some("Strict"),
+            SameSite::Unset => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::{MozCookie, SameSite};
+
+    #[test]
+    fn reads_schema_without_newer_columns() {
+        // The table libsoup creates for Epiphany.
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "create table moz_cookies (id integer primary key, name text, value text,
+                    host text, path text, expiry integer, lastAccessed integer,
+                    isSecure integer, isHttpOnly integer);
+                insert into moz_cookies values (1, 'a', '1', '.foo.com', '/', 1700000000,
+                    null, 1, 1);",
+            )
+            .unwrap();
+
+        let query = format!(
+            "select {} from moz_cookies",
+            super::columns(&connection).unwrap()
+        );
+        let cookie = connection
+            .query_row(&query, [], MozCookie::from_row)
+            .unwrap();
+
+        assert_eq!(cookie.host, ".foo.com");
+        assert!(cookie.is_http_only);
+        assert_eq!(cookie.same_site, SameSite::Unset);
+        assert_eq!(cookie.creation_time, 0);
+        assert_eq!(cookie.last_accessed, 0);
+        assert_eq!(cookie.origin_attributes, "");
+        assert!(!cookie.is_partitioned);
+    }
+
+    #[test]
+    fn reads_partitioned_attribute() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "create table moz_cookies (id integer primary key, originAttributes text,
+                    name text, value text, host text, path text, expiry integer,
+                    isSecure integer, isPartitionedAttributeSet integer);
+                insert into moz_cookies values
+                    (1, '^partitionKey=%28https%2Cbar.com%29', 'a', '1', 'foo.com', '/', 0, 0, 0),
+                    (2, '^partitionKey=%28https%2Cbar.com%29', 'b', '1', 'foo.com', '/', 0, 1, 1);",
+            )
+            .unwrap();
+
+        let query = format!(
+            "select {} from moz_cookies order by id",
+            super::columns(&connection).unwrap()
+        );
+        let mut s = connection.prepare(&query).unwrap();
+        let partitioned: Vec<_> = s
+            .query_map([], MozCookie::from_row)
+            .unwrap()
+            .map(|cookie| cookie.unwrap().is_partitioned)
+            .collect();
+        assert_eq!(partitioned, [false, true]);
+    }
+}
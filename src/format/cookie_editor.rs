@@ -0,0 +1,195 @@
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cookie::{MozCookie, SameSite};
+
+/// A cookie as exported by the Cookie-Editor and EditThisCookie browser extensions, which
+/// mirror the `cookies.Cookie` type of the WebExtensions API.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Cookie {
+    domain: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expiration_date: Option<f64>,
+    #[serde(default)]
+    host_only: Option<bool>,
+    #[serde(default)]
+    http_only: bool,
+    name: String,
+    #[serde(default = "root_path")]
+    path: String,
+    #[serde(default)]
+    same_site: Option<String>,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    session: bool,
+    value: String,
+}
+
+fn root_path() -> String {
+    String::from("/")
+}
+
+impl From<&MozCookie> for Cookie {
+    fn from(cookie: &MozCookie) -> Self {
+        Cookie {
+            domain: cookie.host.clone(),
+            expiration_date: (!cookie.is_session()).then_some(cookie.expiry as f64),
+            host_only: Some(!cookie.include_subdomains()),
+            http_only: cookie.is_http_only,
+            name: cookie.name.clone(),
+            path: cookie.path.clone(),
+            same_site: Some(same_site(cookie.same_site).into()),
+            secure: cookie.is_secure,
+            session: cookie.is_session(),
+            value: cookie.value.clone(),
+        }
+    }
+}
+
+impl From<Cookie> for MozCookie {
+    fn from(cookie: Cookie) -> Self {
+        // Older exports leave out hostOnly; fall back to the leading dot, as Firefox stores it.
+        let domain = cookie.domain.trim_start_matches('.');
+        let host = match cookie.host_only {
+            Some(true) => domain.into(),
+            Some(false) => format!(".{}", domain),
+            None => cookie.domain.clone(),
+        };
+
+        let expiry = match cookie.expiration_date {
+            Some(date) if !cookie.session => date as i64,
+            _ => 0,
+        };
+
+        MozCookie {
+            host,
+            path: cookie.path,
+            name: cookie.name,
+            value: cookie.value,
+            expiry,
+            is_secure: cookie.secure,
+            is_http_only: cookie.http_only,
+            same_site: from_same_site(cookie.same_site.as_deref()),
+            // The extensions don't export timestamps; imported cookies keep their file order.
+            creation_time: 0,
+            last_accessed: 0,
+            origin_attributes: String::new(),
+            is_partitioned: false,
+        }
+    }
+}
+
+fn same_site(same_site: SameSite) -> &'static str {
+    match same_site {
+        SameSite::None => "no_restriction",
+        SameSite::Lax => "lax",
+        SameSite::Strict => "strict",
+        SameSite::Unset => "unspecified",
+    }
+}
+
+fn from_same_site(same_site: Option<&str>) -> SameSite {
+    match same_site {
+        Some("no_restriction") | Some("none") => SameSite::None,
+        Some("lax") => SameSite::Lax,
+        Some("strict") => SameSite::Strict,
+        _ => SameSite::Unset,
+    }
+}
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    let cookies: Vec<_> = cookies.iter().map(Cookie::from).collect();
+    serde_json::to_writer_pretty(&mut *out, &cookies)?;
+    writeln!(out)
+}
+
+/// Reads the cookies in a Cookie-Editor or EditThisCookie json export.
+pub fn read(input: impl Read) -> serde_json::Result<Vec<MozCookie>> {
+    let cookies: Vec<Cookie> = serde_json::from_reader(input)?;
+    Ok(cookies.into_iter().map(MozCookie::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::{MozCookie, SameSite};
+
+    #[test]
+    fn writes_extension_json() {
+        let cookie = MozCookie {
+            is_http_only: true,
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        super::write(&mut buf, &[cookie]).unwrap();
+
+        let cookies: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(
+            cookies,
+            serde_json::json!([{
+                "domain": ".foo.com",
+                "expirationDate": 1700000000.0,
+                "hostOnly": false,
+                "httpOnly": true,
+                "name": "a",
+                "path": "/",
+                "sameSite": "lax",
+                "secure": false,
+                "session": false,
+                "value": "1",
+            }])
+        );
+    }
+
+    #[test]
+    fn reads_extension_json() {
+        let json = r#"[
+            {
+                "domain": "foo.com",
+                "expirationDate": 1700000000.5,
+                "hostOnly": false,
+                "httpOnly": false,
+                "name": "a",
+                "path": "/",
+                "sameSite": "no_restriction",
+                "secure": true,
+                "session": false,
+                "storeId": "0",
+                "value": "1",
+                "id": 1
+            },
+            {
+                "domain": "www.foo.com",
+                "hostOnly": true,
+                "name": "b",
+                "sameSite": null,
+                "session": true,
+                "value": "2"
+            }
+        ]"#;
+
+        let cookies = super::read(json.as_bytes()).unwrap();
+        let summary: Vec<_> = cookies
+            .iter()
+            .map(|c| {
+                (
+                    c.host.as_str(),
+                    c.path.as_str(),
+                    c.expiry,
+                    c.is_secure,
+                    c.same_site,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (".foo.com", "/", 1700000000, true, SameSite::None),
+                ("www.foo.com", "/", 0, false, SameSite::Unset),
+            ]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::cookie::MozCookie;
+
+/// A cookie as serialized by the `cookie_store` crate, one per line, for
+/// `CookieStore::load_json`.
+#[derive(Serialize)]
+struct Cookie<'a> {
+    raw_cookie: String,
+    path: (&'a str, bool),
+    domain: Domain<'a>,
+    expires: Expiration,
+}
+
+#[derive(Serialize)]
+enum Domain<'a> {
+    HostOnly(&'a str),
+    Suffix(&'a str),
+}
+
+#[derive(Serialize)]
+enum Expiration {
+    AtUtc(String),
+    SessionEnd,
+}
+
+impl<'a> From<&'a MozCookie> for Cookie<'a> {
+    fn from(cookie: &'a MozCookie) -> Self {
+        let domain = match cookie.host.strip_prefix('.') {
+            Some(domain) => Domain::Suffix(domain),
+            None => Domain::HostOnly(&cookie.host),
+        };
+
+        // Expiry times past the range chrono can represent are as good as forever.
+        let expires = match cookie.expires() {
+            _ if cookie.is_session() => Expiration::SessionEnd,
+            Some(time) => Expiration::AtUtc(time.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            None => Expiration::AtUtc(String::from("9999-12-31T23:59:59Z")),
+        };
+
+        Cookie {
+            raw_cookie: raw_cookie(cookie),
+            // Firefox doesn't record whether the path came from an attribute; treat it as though
+            // it did, since the browser will only ever send it to that path.
+            path: (&cookie.path, true),
+            domain,
+            expires,
+        }
+    }
+}
+
+/// Renders the name, value and flags the way the `cookie` crate would; everything else is
+/// carried in the other fields.
+fn raw_cookie(cookie: &MozCookie) -> String {
+    let mut raw = format!("{}={}", cookie.name, cookie.value);
+    if cookie.is_http_only {
+        raw.push_str("; HttpOnly");
+    }
+
+    if let Some(same_site) = cookie.same_site.attribute() {
+        raw.push_str("; SameSite=");
+        raw.push_str(same_site);
+    }
+
+    if cookie.is_secure {
+        raw.push_str("; Secure");
+    }
+
+    raw
+}
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    for cookie in cookies {
+        serde_json::to_writer(&mut *out, &Cookie::from(cookie))?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::MozCookie;
+
+    #[test]
+    fn writes_cookie_store_lines() {
+        let domain = MozCookie {
+            is_secure: true,
+            is_http_only: true,
+            ..crate::format::tests::cookie()
+        };
+
+        let host_only = MozCookie {
+            host: String::from("foo.com"),
+            path: String::from("/app"),
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        super::write(&mut buf, &[domain, host_only]).unwrap();
+
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                serde_json::json!({
+                    "raw_cookie": "a=1; HttpOnly; SameSite=Lax; Secure",
+                    "path": ["/", true],
+                    "domain": { "Suffix": "foo.com" },
+                    "expires": { "AtUtc": "2023-11-14T22:13:20Z" },
+                }),
+                serde_json::json!({
+                    "raw_cookie": "a=1; SameSite=Lax",
+                    "path": ["/app", true],
+                    "domain": { "HostOnly": "foo.com" },
+                    "expires": { "AtUtc": "2023-11-14T22:13:20Z" },
+                }),
+            ]
+        );
+    }
+}
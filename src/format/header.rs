@@ -0,0 +1,80 @@
+use std::io::{self, Write};
+
+use crate::{cookie::MozCookie, matching::Selection};
+
+/// Writes a `Cookie` header for each selection, leaving out selections without cookies.
+pub fn write(out: &mut impl Write, selections: &[Selection]) -> io::Result<()> {
+    for selection in selections.iter().filter(|s| !s.cookies.is_empty()) {
+        writeln!(out, "Cookie: {}", header_value(&selection.cookies))?;
+    }
+    Ok(())
+}
+
+pub fn write_curl(out: &mut impl Write, selections: &[Selection]) -> io::Result<()> {
+    for selection in selections.iter().filter(|s| !s.cookies.is_empty()) {
+        let header = format!("Cookie: {}", header_value(&selection.cookies));
+        writeln!(out, "-H {}", shell_quote(&header))?;
+    }
+    Ok(())
+}
+
+/// Joins cookies into the value of a `Cookie` header, as described in RFC 6265 section 5.4.
+pub fn header_value(cookies: &[MozCookie]) -> String {
+    let pairs: Vec<_> = cookies
+        .iter()
+        .map(|cookie| {
+            // Browsers send nameless cookies as a bare value.
+            if cookie.name.is_empty() {
+                cookie.value.clone()
+            } else {
+                format!("{}={}", cookie.name, cookie.value)
+            }
+        })
+        .collect();
+    pairs.join("; ")
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cookie::MozCookie,
+        matching::{Selection, Target},
+    };
+
+    #[test]
+    fn writes_one_header_per_target_with_cookies() {
+        let cookie = crate::format::tests::cookie();
+        let selections = [
+            Selection {
+                target: Target::parse("foo.com").unwrap(),
+                cookies: vec![
+                    cookie.clone(),
+                    MozCookie {
+                        name: String::from("b"),
+                        value: String::from("it's"),
+                        ..cookie
+                    },
+                ],
+            },
+            Selection {
+                target: Target::parse("bar.com").unwrap(),
+                cookies: Vec::new(),
+            },
+        ];
+
+        let mut buf = Vec::new();
+        super::write(&mut buf, &selections).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "Cookie: a=1; b=it's\n");
+
+        let mut buf = Vec::new();
+        super::write_curl(&mut buf, &selections).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "-H 'Cookie: a=1; b=it'\\''s'\n"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::io::{self, Write};
+
+use crate::cookie::MozCookie;
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, cookies)?;
+    writeln!(out)
+}
+
+pub fn write_lines(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    for cookie in cookies {
+        serde_json::to_writer(&mut *out, cookie)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn json_lines_carry_every_field() {
+        let cookie = crate::format::tests::cookie();
+        let mut buf = Vec::new();
+        super::write_lines(&mut buf, &[cookie.clone(), cookie]).unwrap();
+
+        let text = String::from_utf8(buf).unwrap();
+        let expected = "{\"host\":\".foo.com\",\"path\":\"/\",\"name\":\"a\",\"value\":\"1\",\
+            \"expiry\":1700000000,\"secure\":false,\"httpOnly\":false,\"sameSite\":\"lax\",\
+            \"creationTime\":1600000000000000,\"lastAccessed\":1650000000000000,\
+            \"originAttributes\":\"\",\"partitioned\":false}\n";
+        assert_eq!(text, expected.repeat(2));
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::{self, Write};
+
+use crate::cookie::MozCookie;
+
+static LWP_HEADER: &str = "#LWP-Cookies-2.0";
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    writeln!(out, "{}", LWP_HEADER)?;
+
+    for cookie in cookies {
+        writeln!(out, "Set-Cookie3: {}", set_cookie3(cookie))?;
+    }
+
+    Ok(())
+}
+
+/// Mirrors `lwp_cookie_str` from Python's `http.cookiejar`, attribute order included.
+fn set_cookie3(cookie: &MozCookie) -> String {
+    let expires = cookie
+        .expires()
+        .map(|time| time.format("%Y-%m-%d %H:%M:%SZ").to_string());
+
+    let mut words = vec![
+        (cookie.name.as_str(), Some(cookie.value.as_str())),
+        ("path", Some(cookie.path.as_str())),
+        ("domain", Some(cookie.host.as_str())),
+        ("path_spec", None),
+    ];
+
+    if cookie.include_subdomains() {
+        words.push(("domain_dot", None));
+    }
+
+    if cookie.is_secure {
+        words.push(("secure", None));
+    }
+
+    if let Some(expires) = &expires {
+        words.push(("expires", Some(expires)));
+    }
+
+    if cookie.is_session() {
+        words.push(("discard", None));
+    }
+
+    // cookiejar keeps HttpOnly among its nonstandard attributes and writes out their values with
+    // `str`, hence the odd-looking value.
+    if cookie.is_http_only {
+        words.push(("HttpOnly", Some("None")));
+    }
+
+    words.push(("version", Some("0")));
+
+    let words: Vec<_> = words
+        .into_iter()
+        .map(|(key, value)| match value {
+            Some(value) => format!("{}={}", key, quote(value)),
+            None => key.into(),
+        })
+        .collect();
+    words.join("; ")
+}
+
+/// Quotes anything but a plain word, as `join_header_words` does.
+fn quote(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return value.into();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::MozCookie;
+
+    #[test]
+    fn writes_set_cookie3_lines() {
+        let domain = MozCookie {
+            value: String::from("x=\"y\""),
+            is_secure: true,
+            is_http_only: true,
+            ..crate::format::tests::cookie()
+        };
+
+        let host_only = MozCookie {
+            host: String::from("foo.com"),
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        super::write(&mut buf, &[domain, host_only]).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "#LWP-Cookies-2.0\n\
+            Set-Cookie3: a=\"x=\\\"y\\\"\"; path=\"/\"; domain=\".foo.com\"; path_spec; domain_dot; \
+            secure; expires=\"2023-11-14 22:13:20Z\"; HttpOnly=None; version=0\n\
+            Set-Cookie3: a=1; path=\"/\"; domain=\"foo.com\"; path_spec; \
+            expires=\"2023-11-14 22:13:20Z\"; version=0\n"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+pub mod cookie_editor;
+mod cookie_store;
+mod har;
+mod header;
+mod json;
+mod lwp;
+mod netscape;
+mod playwright;
+mod selenium;
+mod set_cookie;
+mod template;
+mod tough_cookie;
+
+use std::io::{self, Write};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::ArgEnum;
+
+use crate::matching::{self, Selection};
+
+pub use template::Template;
+
+#[derive(Clone, Copy, Debug, ArgEnum)]
+pub enum Format {
+    /// Netscape cookies.txt, as read by curl and youtube-dl
+    Netscape,
+    /// a json array of cookie objects
+    Json,
+    /// one json cookie object per line
+    Jsonl,
+    /// a `Cookie` request header per host or url
+    Header,
+    /// a `Cookie` request header per host or url, as curl arguments
+    Curl,
+    /// playwright's storageState json
+    Playwright,
+    /// json lines for the cookie_store crate's `CookieStore::load_json`
+    CookieStore,
+    /// python's LWPCookieJar (Set-Cookie3)
+    Lwp,
+    /// a har cookies array
+    HarCookies,
+    /// a minimal har log with one request per host or url
+    Har,
+    /// one `Set-Cookie` response header per cookie
+    SetCookie,
+    /// json for the Cookie-Editor and EditThisCookie browser extensions
+    CookieEditor,
+    /// a json array of webdriver cookies for selenium's `add_cookie`
+    Selenium,
+    /// a serialized jar for node's tough-cookie `CookieJar.deserialize`
+    ToughCookie,
+}
+
+impl Format {
+    /// Tests whether the format describes requests to its hosts and urls, which carry only the
+    /// cookies a browser would send rather than every cookie a host holds.
+    pub fn is_request(self) -> bool {
+        matches!(self, Format::Header | Format::Curl | Format::Har)
+    }
+
+    pub fn write(self, out: &mut impl Write, selections: &[Selection]) -> io::Result<()> {
+        match self {
+            Format::Netscape => netscape::write(out, &matching::union(selections)),
+            Format::Json => json::write(out, &matching::union(selections)),
+            Format::Jsonl => json::write_lines(out, &matching::union(selections)),
+            Format::Header => header::write(out, selections),
+            Format::Curl => header::write_curl(out, selections),
+            Format::Playwright => playwright::write(out, &matching::union(selections)),
+            Format::CookieStore => cookie_store::write(out, &matching::union(selections)),
+            Format::Lwp => lwp::write(out, &matching::union(selections)),
+            Format::HarCookies => har::write_cookies(out, selections),
+            Format::Har => har::write(out, selections),
+            Format::SetCookie => set_cookie::write(out, &matching::union(selections)),
+            Format::CookieEditor => cookie_editor::write(out, &matching::union(selections)),
+            Format::Selenium => selenium::write(out, &matching::union(selections)),
+            Format::ToughCookie => tough_cookie::write(out, &matching::union(selections)),
+        }
+    }
+}
+
+/// Writes the selected cookies in a built-in format or through a user template.
+#[derive(Clone, Debug)]
+pub enum Renderer {
+    Format(Format),
+    Template(Template),
+}
+
+impl Renderer {
+    pub fn is_request(&self) -> bool {
+        matches!(self, Renderer::Format(format) if format.is_request())
+    }
+
+    pub fn write(&self, out: &mut impl Write, selections: &[Selection]) -> io::Result<()> {
+        match self {
+            Renderer::Format(format) => format.write(out, selections),
+            Renderer::Template(template) => template.write(out, &matching::union(selections)),
+        }
+    }
+}
+
+/// Formats a time the way JavaScript's `Date.prototype.toISOString` does, as HAR and
+/// tough-cookie expect.
+fn iso_8601(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::cookie::{MozCookie, SameSite};
+
+    /// A domain cookie for `.foo.com` shared by the format tests.
+    pub fn cookie() -> MozCookie {
+        MozCookie {
+            host: String::from(".foo.com"),
+            path: String::from("/"),
+            name: String::from("a"),
+            value: String::from("1"),
+            expiry: 1700000000,
+            is_secure: false,
+            is_http_only: false,
+            same_site: SameSite::Lax,
+            creation_time: 1600000000000000,
+            last_accessed: 1650000000000000,
+            origin_attributes: String::new(),
+            is_partitioned: false,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+};
+
+use crate::cookie::MozCookie;
+
+static COOKIE_FILE_HEADER: &str = "# Netscape HTTP Cookie File
+# http://curl.haxx.se/rfc/cookie_spec.html
+# This is a generated file!  Do not edit.
+# ALL SPACES MUST BE TABS! - IT WILL THROW AN ERROR!";
+
+/// Marks HttpOnly cookies in Netscape files. curl, yt-dlp and Python's `MozillaCookieJar` all
+/// strip it from the domain column on read; anything in dognap that reads these files must too.
+static HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+impl MozCookie {
+    fn fmt(&self) -> MozCookieFmt<'_> {
+        MozCookieFmt(self)
+    }
+}
+
+struct MozCookieFmt<'a>(&'a MozCookie);
+
+impl Display for MozCookieFmt<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_http_only {
+            f.write_str(HTTP_ONLY_PREFIX)?;
+        }
+
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.0.host,
+            netscape_bool(self.0.include_subdomains()),
+            self.0.path,
+            netscape_bool(self.0.is_secure),
+            self.0.expiry,
+            self.0.name,
+            self.0.value
+        )
+    }
+}
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    writeln!(out, "{}\n", COOKIE_FILE_HEADER)?;
+
+    for cookie in cookies {
+        writeln!(out, "{}", cookie.fmt())?;
+    }
+
+    Ok(())
+}
+
+fn netscape_bool(value: bool) -> &'static str {
+    if value {
+        "TRUE"
+    } else {
+        "FALSE"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::MozCookie;
+
+    #[test]
+    fn netscape_line_carries_flags() {
+        let domain = MozCookie {
+            is_secure: true,
+            ..crate::format::tests::cookie()
+        };
+
+        let host_only = MozCookie {
+            host: String::from("foo.com"),
+            is_secure: false,
+            ..domain.clone()
+        };
+
+        assert_eq!(
+            domain.fmt().to_string(),
+            ".foo.com\tTRUE\t/\tTRUE\t1700000000\ta\t1"
+        );
+        assert_eq!(
+            host_only.fmt().to_string(),
+            "foo.com\tFALSE\t/\tFALSE\t1700000000\ta\t1"
+        );
+    }
+
+    #[test]
+    fn netscape_line_marks_http_only() {
+        let cookie = MozCookie {
+            is_secure: true,
+            is_http_only: true,
+            ..crate::format::tests::cookie()
+        };
+
+        assert_eq!(
+            cookie.fmt().to_string(),
+            "#HttpOnly_.foo.com\tTRUE\t/\tTRUE\t1700000000\ta\t1"
+        );
+    }
+}
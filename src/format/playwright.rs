@@ -0,0 +1,125 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::cookie::MozCookie;
+
+#[derive(Serialize)]
+struct StorageState<'a> {
+    cookies: Vec<Cookie<'a>>,
+    // Local storage lives outside the cookie database, so there is nothing to put here.
+    origins: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Cookie<'a> {
+    name: &'a str,
+    value: &'a str,
+    domain: &'a str,
+    path: &'a str,
+    expires: i64,
+    http_only: bool,
+    secure: bool,
+    same_site: &'static str,
+}
+
+impl<'a> From<&'a MozCookie> for Cookie<'a> {
+    fn from(cookie: &'a MozCookie) -> Self {
+        Cookie {
+            name: &cookie.name,
+            value: &cookie.value,
+            domain: &cookie.host,
+            path: &cookie.path,
+            // Playwright marks session cookies with -1.
+            expires: if cookie.is_session() {
+                -1
+            } else {
+                cookie.expiry
+            },
+            http_only: cookie.is_http_only,
+            secure: cookie.is_secure,
+            // Playwright only knows the three policies a cookie can name. Firefox does not
+            // default to lax, so unset cookies behave as `None`, unless they are not secure
+            // and Chromium would refuse them.
+            same_site: match cookie.same_site_attribute() {
+                Some(same_site) => same_site,
+                None if cookie.is_secure => "None",
+                None => "Lax",
+            },
+        }
+    }
+}
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    let state = StorageState {
+        cookies: cookies.iter().map(Cookie::from).collect(),
+        origins: Vec::new(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &state)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::{MozCookie, SameSite};
+
+    #[test]
+    fn keeps_insecure_cookies_off_same_site_none() {
+        let cookies: Vec<_> = [
+            (SameSite::None, false),
+            (SameSite::Unset, false),
+            (SameSite::Unset, true),
+        ]
+        .into_iter()
+        .map(|(same_site, is_secure)| MozCookie {
+            same_site,
+            is_secure,
+            ..crate::format::tests::cookie()
+        })
+        .collect();
+
+        let mut buf = Vec::new();
+        super::write(&mut buf, &cookies).unwrap();
+
+        let state: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        let same_site: Vec<_> = state["cookies"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|cookie| cookie["sameSite"].as_str().unwrap())
+            .collect();
+        assert_eq!(same_site, ["Lax", "Lax", "None"]);
+    }
+
+    #[test]
+    fn writes_storage_state() {
+        let cookie = MozCookie {
+            same_site: SameSite::Strict,
+            is_http_only: true,
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        super::write(&mut buf, &[cookie]).unwrap();
+
+        let state: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(
+            state,
+            serde_json::json!({
+                "cookies": [{
+                    "name": "a",
+                    "value": "1",
+                    "domain": ".foo.com",
+                    "path": "/",
+                    "expires": 1700000000,
+                    "httpOnly": true,
+                    "secure": false,
+                    "sameSite": "Strict",
+                }],
+                "origins": [],
+            })
+        );
+    }
+}
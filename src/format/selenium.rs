@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::cookie::MozCookie;
+
+/// A cookie as WebDriver serializes it, ready to pass to Selenium's `add_cookie`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Cookie<'a> {
+    name: &'a str,
+    value: &'a str,
+    domain: &'a str,
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expiry: Option<i64>,
+    secure: bool,
+    http_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    same_site: Option<&'static str>,
+}
+
+impl<'a> From<&'a MozCookie> for Cookie<'a> {
+    fn from(cookie: &'a MozCookie) -> Self {
+        Cookie {
+            name: &cookie.name,
+            value: &cookie.value,
+            domain: &cookie.host,
+            path: &cookie.path,
+            expiry: (!cookie.is_session()).then_some(cookie.expiry),
+            secure: cookie.is_secure,
+            http_only: cookie.is_http_only,
+            // Leaving sameSite out lets the driver apply the browser's own default, which is
+            // also the only way chromedriver takes an insecure cookie without a policy.
+            same_site: cookie.same_site_attribute(),
+        }
+    }
+}
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    let cookies: Vec<_> = cookies.iter().map(Cookie::from).collect();
+    serde_json::to_writer_pretty(&mut *out, &cookies)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::{MozCookie, SameSite};
+
+    #[test]
+    fn writes_webdriver_cookies() {
+        let session = MozCookie {
+            host: String::from("foo.com"),
+            expiry: 0,
+            same_site: SameSite::Unset,
+            is_secure: true,
+            ..crate::format::tests::cookie()
+        };
+
+        let insecure = MozCookie {
+            name: String::from("b"),
+            same_site: SameSite::None,
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        super::write(
+            &mut buf,
+            &[crate::format::tests::cookie(), session, insecure],
+        )
+        .unwrap();
+
+        let cookies: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(
+            cookies,
+            serde_json::json!([
+                {
+                    "name": "a",
+                    "value": "1",
+                    "domain": ".foo.com",
+                    "path": "/",
+                    "expiry": 1700000000,
+                    "secure": false,
+                    "httpOnly": false,
+                    "sameSite": "Lax",
+                },
+                {
+                    "name": "a",
+                    "value": "1",
+                    "domain": "foo.com",
+                    "path": "/",
+                    "secure": true,
+                    "httpOnly": false,
+                },
+                {
+                    "name": "b",
+                    "value": "1",
+                    "domain": ".foo.com",
+                    "path": "/",
+                    "expiry": 1700000000,
+                    "secure": false,
+                    "httpOnly": false,
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::{self, Write};
+
+use chrono::Utc;
+
+use crate::cookie::MozCookie;
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    let now = Utc::now().timestamp();
+    for cookie in cookies {
+        writeln!(out, "Set-Cookie: {}", set_cookie(cookie, now))?;
+    }
+    Ok(())
+}
+
+/// Renders a cookie as the value of the `Set-Cookie` header that would recreate it.
+fn set_cookie(cookie: &MozCookie, now: i64) -> String {
+    let mut header = format!("{}={}", cookie.name, cookie.value);
+
+    // Leaving out the domain attribute is what makes a cookie host-only.
+    if let Some(domain) = cookie.host.strip_prefix('.') {
+        header.push_str("; Domain=");
+        header.push_str(domain);
+    }
+
+    header.push_str("; Path=");
+    header.push_str(&cookie.path);
+
+    if let Some(expires) = cookie.expires() {
+        header.push_str("; Expires=");
+        header.push_str(&expires.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+    }
+
+    if !cookie.is_session() {
+        header.push_str("; Max-Age=");
+        header.push_str(&(cookie.expiry - now).max(0).to_string());
+    }
+
+    if cookie.is_secure {
+        header.push_str("; Secure");
+    }
+
+    if cookie.is_http_only {
+        header.push_str("; HttpOnly");
+    }
+
+    if let Some(same_site) = cookie.same_site_attribute() {
+        header.push_str("; SameSite=");
+        header.push_str(same_site);
+    }
+
+    if cookie.is_partitioned {
+        header.push_str("; Partitioned");
+    }
+
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::{MozCookie, SameSite};
+
+    #[test]
+    fn renders_every_attribute() {
+        let cookie = MozCookie {
+            is_secure: true,
+            is_http_only: true,
+            same_site: SameSite::None,
+            origin_attributes: String::from("^partitionKey=%28https%2Cbar.com%29"),
+            is_partitioned: true,
+            ..crate::format::tests::cookie()
+        };
+
+        assert_eq!(
+            super::set_cookie(&cookie, 1699999000),
+            "a=1; Domain=foo.com; Path=/; Expires=Tue, 14 Nov 2023 22:13:20 GMT; Max-Age=1000; \
+            Secure; HttpOnly; SameSite=None; Partitioned"
+        );
+    }
+
+    #[test]
+    fn insecure_cookies_drop_same_site_none() {
+        // Partitioned by Firefox rather than by the site.
+        let cookie = MozCookie {
+            same_site: SameSite::None,
+            origin_attributes: String::from("^partitionKey=%28https%2Cbar.com%29"),
+            ..crate::format::tests::cookie()
+        };
+
+        assert_eq!(
+            super::set_cookie(&cookie, 1800000000),
+            "a=1; Domain=foo.com; Path=/; Expires=Tue, 14 Nov 2023 22:13:20 GMT; Max-Age=0"
+        );
+    }
+
+    #[test]
+    fn host_only_cookies_have_no_domain() {
+        let cookie = MozCookie {
+            host: String::from("foo.com"),
+            same_site: SameSite::Unset,
+            ..crate::format::tests::cookie()
+        };
+
+        assert_eq!(
+            super::set_cookie(&cookie, 1800000000),
+            "a=1; Path=/; Expires=Tue, 14 Nov 2023 22:13:20 GMT; Max-Age=0"
+        );
+    }
+}
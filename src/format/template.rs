@@ -0,0 +1,363 @@
+use std::io::{self, Write};
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
+
+use crate::cookie::MozCookie;
+
+/// Everything but the characters RFC 3986 leaves unreserved.
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// A user-supplied output format, rendered once per cookie between an optional header and
+/// footer.
+///
+/// Placeholders look like `{name}` or `{expiry:rfc3339}` and may chain several formatters, as
+/// in `{value:url-encode:json-escape}`. `{{` and `}}` stand for literal braces.
+#[derive(Clone, Debug)]
+pub struct Template {
+    header: Option<String>,
+    cookie: Vec<Segment>,
+    footer: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Literal(String),
+    Field(Field, Vec<Formatter>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Field {
+    Host,
+    Path,
+    Name,
+    Value,
+    Expiry,
+    Secure,
+    HttpOnly,
+    SameSite,
+    CreationTime,
+    LastAccessed,
+    OriginAttributes,
+    IncludeSubdomains,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Formatter {
+    Rfc3339,
+    Unix,
+    UrlEncode,
+    JsonEscape,
+}
+
+enum Value {
+    Text(String),
+    /// A raw column value along with the same moment in Unix seconds.
+    Time(i64, i64),
+}
+
+impl Template {
+    /// Parses a template for a single cookie, with no header or footer.
+    pub fn parse(cookie: &str) -> anyhow::Result<Self> {
+        Ok(Template {
+            header: None,
+            cookie: parse_segments(cookie)?,
+            footer: None,
+        })
+    }
+
+    /// Parses a template file. Lines reading `--- header ---`, `--- cookie ---` and
+    /// `--- footer ---` begin each section; anything before the first of them is the cookie
+    /// section. Header and footer are written as-is.
+    pub fn parse_file(text: &str) -> anyhow::Result<Self> {
+        let mut header = None;
+        let mut cookie = String::new();
+        let mut footer = None;
+
+        let mut section = &mut cookie;
+        for line in text.lines() {
+            section = match line.trim() {
+                "--- header ---" => header.insert(String::new()),
+                "--- cookie ---" => &mut cookie,
+                "--- footer ---" => footer.insert(String::new()),
+                _ => {
+                    section.push_str(line);
+                    section.push('\n');
+                    continue;
+                }
+            };
+        }
+
+        let trim = |section: String| section.trim_end_matches('\n').to_owned();
+        Ok(Template {
+            header: header.map(trim),
+            cookie: parse_segments(&trim(cookie))?,
+            footer: footer.map(trim),
+        })
+    }
+
+    pub fn write(&self, out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+        if let Some(header) = &self.header {
+            writeln!(out, "{}", header)?;
+        }
+
+        for cookie in cookies {
+            for segment in &self.cookie {
+                match segment {
+                    Segment::Literal(text) => out.write_all(text.as_bytes())?,
+                    Segment::Field(field, formatters) => {
+                        let value = formatters
+                            .iter()
+                            .fold(field.value(cookie), |value, formatter| {
+                                formatter.apply(value)
+                            });
+                        write!(out, "{}", value.into_text())?;
+                    }
+                }
+            }
+            writeln!(out)?;
+        }
+
+        if let Some(footer) = &self.footer {
+            writeln!(out, "{}", footer)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_segments(template: &str) -> anyhow::Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| anyhow::anyhow!("unclosed placeholder in template"))?;
+
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(parse_placeholder(&rest[..end])?);
+                chars = rest[end + 1..].chars();
+            }
+            '}' => anyhow::bail!("unmatched `}}` in template; write `}}}}` for a literal brace"),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+fn parse_placeholder(placeholder: &str) -> anyhow::Result<Segment> {
+    let mut parts = placeholder.split(':').map(str::trim);
+    let name = parts.next().unwrap_or_default();
+    let field =
+        Field::parse(name).ok_or_else(|| anyhow::anyhow!("unknown field in template: {}", name))?;
+
+    let mut is_time = field.is_time();
+    let mut formatters = Vec::new();
+    for name in parts {
+        let formatter = Formatter::parse(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown formatter in template: {}", name))?;
+
+        if formatter.needs_time() && !is_time {
+            anyhow::bail!("`{}` only applies to times: {{{}}}", name, placeholder);
+        }
+
+        is_time = false;
+        formatters.push(formatter);
+    }
+
+    Ok(Segment::Field(field, formatters))
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        let field = match name {
+            "host" => Field::Host,
+            "path" => Field::Path,
+            "name" => Field::Name,
+            "value" => Field::Value,
+            "expiry" => Field::Expiry,
+            "secure" => Field::Secure,
+            "httpOnly" => Field::HttpOnly,
+            "sameSite" => Field::SameSite,
+            "creationTime" => Field::CreationTime,
+            "lastAccessed" => Field::LastAccessed,
+            "originAttributes" => Field::OriginAttributes,
+            "includeSubdomains" => Field::IncludeSubdomains,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    fn is_time(self) -> bool {
+        matches!(
+            self,
+            Field::Expiry | Field::CreationTime | Field::LastAccessed
+        )
+    }
+
+    fn value(self, cookie: &MozCookie) -> Value {
+        match self {
+            Field::Host => Value::Text(cookie.host.clone()),
+            Field::Path => Value::Text(cookie.path.clone()),
+            Field::Name => Value::Text(cookie.name.clone()),
+            Field::Value => Value::Text(cookie.value.clone()),
+            Field::Expiry => Value::Time(cookie.expiry, cookie.expiry),
+            Field::Secure => Value::Text(cookie.is_secure.to_string()),
+            Field::HttpOnly => Value::Text(cookie.is_http_only.to_string()),
+            Field::SameSite => Value::Text(cookie.same_site.as_str().into()),
+            Field::CreationTime => {
+                Value::Time(cookie.creation_time, cookie.creation_time / 1_000_000)
+            }
+            Field::LastAccessed => {
+                Value::Time(cookie.last_accessed, cookie.last_accessed / 1_000_000)
+            }
+            Field::OriginAttributes => Value::Text(cookie.origin_attributes.clone()),
+            Field::IncludeSubdomains => Value::Text(cookie.include_subdomains().to_string()),
+        }
+    }
+}
+
+impl Formatter {
+    fn parse(name: &str) -> Option<Self> {
+        let formatter = match name {
+            "rfc3339" => Formatter::Rfc3339,
+            "unix" => Formatter::Unix,
+            "url-encode" => Formatter::UrlEncode,
+            "json-escape" => Formatter::JsonEscape,
+            _ => return None,
+        };
+        Some(formatter)
+    }
+
+    fn needs_time(self) -> bool {
+        matches!(self, Formatter::Rfc3339 | Formatter::Unix)
+    }
+
+    fn apply(self, value: Value) -> Value {
+        match (self, value) {
+            // 0 stands for a session cookie's expiry, or a time the browser never recorded.
+            (Formatter::Rfc3339 | Formatter::Unix, Value::Time(0, _)) => Value::Text(String::new()),
+            (Formatter::Rfc3339, Value::Time(raw, seconds)) => {
+                match Utc.timestamp_opt(seconds, 0) {
+                    chrono::LocalResult::Single(time) => {
+                        Value::Text(time.to_rfc3339_opts(SecondsFormat::Secs, true))
+                    }
+                    _ => Value::Text(raw.to_string()),
+                }
+            }
+            (Formatter::Unix, Value::Time(_, seconds)) => Value::Text(seconds.to_string()),
+            (Formatter::UrlEncode, value) => {
+                let text = value.into_text();
+                Value::Text(
+                    percent_encoding::utf8_percent_encode(&text, URL_ENCODE_SET).to_string(),
+                )
+            }
+            (Formatter::JsonEscape, value) => {
+                let quoted = serde_json::Value::String(value.into_text()).to_string();
+                Value::Text(quoted[1..quoted.len() - 1].to_owned())
+            }
+            // Ruled out when the template is parsed.
+            (_, value) => value,
+        }
+    }
+}
+
+impl Value {
+    fn into_text(self) -> String {
+        match self {
+            Value::Text(text) => text,
+            Value::Time(raw, _) => raw.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+    use crate::cookie::MozCookie;
+
+    fn render(template: &Template) -> String {
+        let cookie = MozCookie {
+            value: String::from("a b\"c"),
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        template.write(&mut buf, &[cookie]).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn renders_fields_and_formatters() {
+        let template = Template::parse(
+            "{{{name}}}={value:url-encode}; expires {expiry:rfc3339} ({expiry}), \
+            created {creationTime:unix}, \"{value:json-escape}\" {sameSite} {includeSubdomains}",
+        )
+        .unwrap();
+
+        assert_eq!(
+            render(&template),
+            "{a}=a%20b%22c; expires 2023-11-14T22:13:20Z (1700000000), created 1600000000, \
+            \"a b\\\"c\" lax true\n"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_times_empty() {
+        let template =
+            Template::parse("[{expiry:rfc3339}] [{creationTime:unix}] [{lastAccessed}]").unwrap();
+        let cookie = MozCookie {
+            expiry: 0,
+            creation_time: 0,
+            last_accessed: 0,
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        template.write(&mut buf, &[cookie]).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "[] [] [0]\n");
+    }
+
+    #[test]
+    fn renders_sections_from_file() {
+        let template = Template::parse_file(
+            "--- header ---\n[\n--- cookie ---\n  \"{name}\",\n\n--- footer ---\n]\n",
+        )
+        .unwrap();
+
+        assert_eq!(render(&template), "[\n  \"a\",\n]\n");
+    }
+
+    #[test]
+    fn rejects_bad_templates() {
+        assert!(Template::parse("{nope}").is_err());
+        assert!(Template::parse("{name:rfc3339}").is_err());
+        assert!(Template::parse("{expiry:unix:unix}").is_err());
+        assert!(Template::parse("{name").is_err());
+        assert!(Template::parse("name}").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use std::io::{self, Write};
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+
+use super::iso_8601;
+use crate::cookie::MozCookie;
+
+/// A serialized `tough-cookie` jar, as produced by `CookieJar.serialize` and read back by
+/// `CookieJar.deserialize`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Jar<'a> {
+    version: &'static str,
+    store_type: &'static str,
+    reject_public_suffixes: bool,
+    cookies: Vec<Cookie<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Cookie<'a> {
+    key: &'a str,
+    value: &'a str,
+    /// Left out for session cookies, which tough-cookie marks as expiring at `Infinity`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<String>,
+    domain: &'a str,
+    path: &'a str,
+    secure: bool,
+    http_only: bool,
+    host_only: bool,
+    /// Left out when unknown, as for imported cookies, so tough-cookie keeps its own default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    creation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_accessed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    same_site: Option<String>,
+}
+
+impl<'a> From<&'a MozCookie> for Cookie<'a> {
+    fn from(cookie: &'a MozCookie) -> Self {
+        Cookie {
+            key: &cookie.name,
+            value: &cookie.value,
+            expires: cookie.expires().map(iso_8601),
+            // tough-cookie keeps domains canonical, without a leading dot.
+            domain: cookie.host.trim_start_matches('.'),
+            path: &cookie.path,
+            secure: cookie.is_secure,
+            http_only: cookie.is_http_only,
+            host_only: !cookie.include_subdomains(),
+            creation: from_micros(cookie.creation_time).map(iso_8601),
+            last_accessed: from_micros(cookie.last_accessed).map(iso_8601),
+            // tough-cookie spells policies in lowercase.
+            same_site: cookie.same_site.attribute().map(str::to_ascii_lowercase),
+        }
+    }
+}
+
+/// Converts one of Firefox's microsecond timestamps, where 0 means unknown; JavaScript dates
+/// only go down to millis.
+fn from_micros(micros: i64) -> Option<DateTime<Utc>> {
+    if micros == 0 {
+        return None;
+    }
+    Utc.timestamp_millis_opt(micros / 1000).single()
+}
+
+pub fn write(out: &mut impl Write, cookies: &[MozCookie]) -> io::Result<()> {
+    let jar = Jar {
+        version: "tough-cookie@4.1.3",
+        store_type: "MemoryCookieStore",
+        reject_public_suffixes: true,
+        cookies: cookies.iter().map(Cookie::from).collect(),
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &jar)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie::MozCookie;
+
+    #[test]
+    fn writes_serialized_jar() {
+        let cookie = MozCookie {
+            is_secure: true,
+            ..crate::format::tests::cookie()
+        };
+        let imported = MozCookie {
+            host: String::from("foo.com"),
+            creation_time: 0,
+            last_accessed: 0,
+            ..crate::format::tests::cookie()
+        };
+
+        let mut buf = Vec::new();
+        super::write(&mut buf, &[cookie, imported]).unwrap();
+
+        let jar: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(
+            jar,
+            serde_json::json!({
+                "version": "tough-cookie@4.1.3",
+                "storeType": "MemoryCookieStore",
+                "rejectPublicSuffixes": true,
+                "cookies": [{
+                    "key": "a",
+                    "value": "1",
+                    "expires": "2023-11-14T22:13:20.000Z",
+                    "domain": "foo.com",
+                    "path": "/",
+                    "secure": true,
+                    "httpOnly": false,
+                    "hostOnly": false,
+                    "creation": "2020-09-13T12:26:40.000Z",
+                    "lastAccessed": "2022-04-15T05:20:00.000Z",
+                    "sameSite": "lax",
+                }, {
+                    "key": "a",
+                    "value": "1",
+                    "expires": "2023-11-14T22:13:20.000Z",
+                    "domain": "foo.com",
+                    "path": "/",
+                    "secure": false,
+                    "httpOnly": false,
+                    "hostOnly": true,
+                    "sameSite": "lax",
+                }],
+            })
+        );
+    }
+}
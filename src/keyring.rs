@@ -0,0 +1,237 @@
+use anyhow::Context;
+use serde::Deserialize;
+use zbus::{
+    blocking::{Connection, Proxy},
+    zvariant::{OwnedObjectPath, OwnedValue, Type, Value},
+};
+
+static SERVICE: &str = "org.freedesktop.secrets";
+static SERVICE_PATH: &str = "/org/freedesktop/secrets";
+static DEFAULT_COLLECTION: &str = "/org/freedesktop/secrets/aliases/default";
+
+/// A secret as returned by `org.freedesktop.Secret.Item.GetSecret`.
+#[derive(Deserialize, Type)]
+struct Secret {
+    _session: OwnedObjectPath,
+    _parameters: Vec<u8>,
+    value: Vec<u8>,
+    _content_type: String,
+}
+
+/// Fetches the secret labelled `label` from the default collection of the Secret Service on
+/// the session bus.
+pub fn password(label: &str) -> anyhow::Result<Vec<u8>> {
+    let connection = Connection::session().context("cannot reach the secret service")?;
+    lookup(&connection, label)?
+        .ok_or_else(|| anyhow::anyhow!("no \"{}\" entry in the keyring", label))
+}
+
+/// Fetches the secret labelled `label` from the default collection, if it holds one.
+///
+/// The attributes Chromium files its password under have changed between versions, but the
+/// label has not, so items are matched by label.
+pub fn lookup(connection: &Connection, label: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    let service = Proxy::new(
+        connection,
+        SERVICE,
+        SERVICE_PATH,
+        "org.freedesktop.Secret.Service",
+    )?;
+
+    // The session bus never leaves the machine, so the unencrypted "plain" algorithm will do.
+    let (_, session): (OwnedValue, OwnedObjectPath) =
+        service.call("OpenSession", &("plain", Value::from("")))?;
+
+    let collection = Proxy::new(
+        connection,
+        SERVICE,
+        DEFAULT_COLLECTION,
+        "org.freedesktop.Secret.Collection",
+    )?;
+    let items: Vec<OwnedObjectPath> = collection.get_property("Items")?;
+
+    for path in items {
+        let item = Proxy::new(
+            connection,
+            SERVICE,
+            path.as_str(),
+            "org.freedesktop.Secret.Item",
+        )?;
+
+        let item_label: String = item.get_property("Label")?;
+        if item_label != label {
+            continue;
+        }
+
+        if item.get_property::<bool>("Locked")? {
+            anyhow::bail!("the keyring holding \"{}\" is locked", label);
+        }
+
+        let secret: Secret = item.call("GetSecret", &(&session,))?;
+        return Ok(Some(secret.value));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Child, Command, Stdio},
+    };
+
+    use serde::Serialize;
+    use zbus::{
+        blocking::{Connection, ConnectionBuilder},
+        dbus_interface,
+        zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type, Value},
+    };
+
+    /// A private session bus that goes away with the test.
+    struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Bus {
+        fn start() -> Bus {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("cannot start dbus-daemon");
+
+            let mut address = String::new();
+            BufReader::new(daemon.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+
+            Bus {
+                daemon,
+                address: address.trim().into(),
+            }
+        }
+
+        fn connect(&self) -> ConnectionBuilder<'static> {
+            ConnectionBuilder::address(self.address.as_str()).unwrap()
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    struct Service;
+
+    #[dbus_interface(name = "org.freedesktop.Secret.Service")]
+    impl Service {
+        fn open_session(
+            &self,
+            _algorithm: &str,
+            _input: Value<'_>,
+        ) -> (OwnedValue, OwnedObjectPath) {
+            (
+                Value::from("").into(),
+                ObjectPath::try_from("/org/freedesktop/secrets/session/1")
+                    .unwrap()
+                    .into(),
+            )
+        }
+    }
+
+    struct Collection;
+
+    #[dbus_interface(name = "org.freedesktop.Secret.Collection")]
+    impl Collection {
+        #[dbus_interface(property)]
+        fn items(&self) -> Vec<OwnedObjectPath> {
+            [
+                "/org/freedesktop/secrets/collection/login/1",
+                "/org/freedesktop/secrets/collection/login/2",
+            ]
+            .into_iter()
+            .map(|path| ObjectPath::try_from(path).unwrap().into())
+            .collect()
+        }
+    }
+
+    #[derive(Serialize, Type)]
+    struct Secret {
+        session: OwnedObjectPath,
+        parameters: Vec<u8>,
+        value: Vec<u8>,
+        content_type: String,
+    }
+
+    struct Item {
+        label: &'static str,
+        secret: &'static [u8],
+    }
+
+    #[dbus_interface(name = "org.freedesktop.Secret.Item")]
+    impl Item {
+        #[dbus_interface(property)]
+        fn label(&self) -> String {
+            self.label.into()
+        }
+
+        #[dbus_interface(property)]
+        fn locked(&self) -> bool {
+            false
+        }
+
+        fn get_secret(&self, session: OwnedObjectPath) -> Secret {
+            Secret {
+                session,
+                parameters: Vec::new(),
+                value: self.secret.into(),
+                content_type: String::from("text/plain"),
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon; run with --ignored"]
+    fn finds_secret_by_label() {
+        let bus = Bus::start();
+
+        let _service = bus
+            .connect()
+            .name("org.freedesktop.secrets")
+            .unwrap()
+            .serve_at("/org/freedesktop/secrets", Service)
+            .unwrap()
+            .serve_at("/org/freedesktop/secrets/aliases/default", Collection)
+            .unwrap()
+            .serve_at(
+                "/org/freedesktop/secrets/collection/login/1",
+                Item {
+                    label: "Other Safe Storage",
+                    secret: b"nope",
+                },
+            )
+            .unwrap()
+            .serve_at(
+                "/org/freedesktop/secrets/collection/login/2",
+                Item {
+                    label: "Chromium Safe Storage",
+                    secret: b"hunter2",
+                },
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let client: Connection = bus.connect().build().unwrap();
+        assert_eq!(
+            super::lookup(&client, "Chromium Safe Storage").unwrap(),
+            Some(b"hunter2".to_vec())
+        );
+        assert_eq!(super::lookup(&client, "Brave Safe Storage").unwrap(), None);
+    }
+}
@@ -0,0 +1,327 @@
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv6Addr},
+};
+
+use anyhow::Context;
+use url::Url;
+
+use crate::cookie::MozCookie;
+
+/// A host or URL naming the cookies to export.
+///
+/// Bare hosts select every cookie the browser holds for that host. Full URLs narrow the
+/// selection to the cookies a request to that exact URL would carry.
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub host: String,
+    /// The URL given, or the root of the host over https when only a host was given.
+    pub url: Url,
+    exact: bool,
+}
+
+impl Target {
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        if !crate::has_scheme(input) {
+            let host = crate::derive_host(input)?;
+            let url = if host.parse::<Ipv6Addr>().is_ok() {
+                Url::parse(&format!("https://[{}]/", host))?
+            } else {
+                Url::parse(&format!("https://{}/", host))?
+            };
+
+            return Ok(Target {
+                host,
+                url,
+                exact: false,
+            });
+        }
+
+        let url = Url::parse(input).with_context(|| format!("invalid url: {}", input))?;
+        Ok(Target {
+            host: crate::url_host(&url)?,
+            url,
+            exact: true,
+        })
+    }
+
+    /// Tests whether the browser would send `cookie` to this target at time `now` (in Unix
+    /// seconds). With `include_subdomains`, cookies set on subdomains of the target also match.
+    pub fn matches(&self, cookie: &MozCookie, include_subdomains: bool, now: i64) -> bool {
+        if !self.domain_matches(&cookie.host, include_subdomains) {
+            return false;
+        }
+
+        if !self.exact {
+            return true;
+        }
+
+        let secure = matches!(self.url.scheme(), "https" | "wss");
+        path_matches(&cookie.path, self.url.path())
+            && (secure || !cookie.is_secure)
+            && (cookie.is_session() || cookie.expiry > now)
+    }
+
+    /// Treats the target as a request to its URL, so that a bare host only selects the cookies
+    /// a request to its root would carry.
+    pub fn into_request(self) -> Self {
+        Target {
+            exact: true,
+            ..self
+        }
+    }
+
+    fn domain_matches(&self, cookie_host: &str, include_subdomains: bool) -> bool {
+        let host = self.host.as_str();
+        match cookie_host.strip_prefix('.') {
+            Some(domain) => {
+                host == domain
+                    || host.parse::<IpAddr>().is_err() && is_subdomain(host, domain)
+                    || include_subdomains && is_subdomain(domain, host)
+            }
+            None => cookie_host == host || include_subdomains && is_subdomain(cookie_host, host),
+        }
+    }
+}
+
+/// The cookies selected by a single target, in the order a browser would send them.
+#[derive(Clone, Debug)]
+pub struct Selection {
+    pub target: Target,
+    pub cookies: Vec<MozCookie>,
+}
+
+pub fn select(
+    targets: &[Target],
+    cookies: &[MozCookie],
+    include_subdomains: bool,
+    now: i64,
+) -> Vec<Selection> {
+    targets
+        .iter()
+        .map(|target| {
+            let mut selected: Vec<_> = cookies
+                .iter()
+                .filter(|cookie| target.matches(cookie, include_subdomains, now))
+                .cloned()
+                .collect();
+            sort(&mut selected);
+
+            Selection {
+                target: target.clone(),
+                cookies: selected,
+            }
+        })
+        .collect()
+}
+
+/// Merges the cookies of every selection, keeping one copy of cookies selected more than once.
+pub fn union(selections: &[Selection]) -> Vec<MozCookie> {
+    let mut seen = HashSet::new();
+    let mut cookies: Vec<_> = selections
+        .iter()
+        .flat_map(|selection| &selection.cookies)
+        .filter(|cookie| {
+            seen.insert((
+                &cookie.host,
+                &cookie.path,
+                &cookie.name,
+                &cookie.origin_attributes,
+            ))
+        })
+        .cloned()
+        .collect();
+    sort(&mut cookies);
+    cookies
+}
+
+/// Orders cookies the way a browser lists them in a `Cookie` header: longer paths first, then
+/// earlier creation times.
+pub fn sort(cookies: &mut [MozCookie]) {
+    cookies.sort_by(|a, b| {
+        b.path
+            .len()
+            .cmp(&a.path.len())
+            .then(a.creation_time.cmp(&b.creation_time))
+    });
+}
+
+/// Lists every `moz_cookies.host` value holding cookies a browser would send to `host`.
+///
+/// Per RFC 6265, that is the host itself (host-only cookies are stored without a leading dot)
+/// along with domain cookies set on the host or any of its parent domains. IP addresses only
+/// ever match themselves.
+pub fn candidate_hosts(host: &str) -> Vec<String> {
+    let mut candidates = vec![host.to_owned()];
+    if host.parse::<IpAddr>().is_ok() {
+        return candidates;
+    }
+
+    let mut domain = host;
+    loop {
+        candidates.push(format!(".{}", domain));
+        match domain.split_once('.') {
+            Some((_, parent)) if !parent.is_empty() => domain = parent,
+            _ => break,
+        }
+    }
+
+    candidates
+}
+
+/// Builds a `like` pattern (escaped with `\`) matching cookies set on subdomains of `host`.
+pub fn subdomain_pattern(host: &str) -> String {
+    let mut pattern = String::from("%.");
+    for c in host.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
+}
+
+fn is_subdomain(host: &str, domain: &str) -> bool {
+    host.len() > domain.len()
+        && host.ends_with(domain)
+        && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+}
+
+/// Path-match as defined in RFC 6265 section 5.1.4.
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Target;
+    use crate::cookie::{MozCookie, SameSite};
+
+    fn cookie(host: &str, path: &str, is_secure: bool, expiry: i64) -> MozCookie {
+        MozCookie {
+            host: host.into(),
+            path: path.into(),
+            name: String::from("a"),
+            value: String::from("1"),
+            expiry,
+            is_secure,
+            is_http_only: false,
+            same_site: SameSite::None,
+            creation_time: 0,
+            last_accessed: 0,
+            origin_attributes: String::new(),
+            is_partitioned: false,
+        }
+    }
+
+    #[test]
+    fn url_applies_request_rules() {
+        let target = Target::parse("https://example.com/app/x").unwrap();
+        let now = 1_700_000_000;
+
+        assert!(target.matches(&cookie(".example.com", "/", true, now + 1), false, now));
+        assert!(target.matches(&cookie("example.com", "/app", false, now + 1), false, now));
+        assert!(target.matches(&cookie("example.com", "/app/", false, now + 1), false, now));
+        assert!(!target.matches(
+            &cookie("example.com", "/application", false, now + 1),
+            false,
+            now
+        ));
+        assert!(!target.matches(&cookie("example.com", "/", false, now), false, now));
+        assert!(target.matches(&cookie("example.com", "/", false, 0), false, now));
+        assert!(!target.matches(&cookie("www.example.com", "/", false, now + 1), false, now));
+
+        let target = Target::parse("http://example.com/").unwrap();
+        assert!(!target.matches(&cookie("example.com", "/", true, now + 1), false, now));
+    }
+
+    #[test]
+    fn bare_host_request_applies_request_rules() {
+        let now = 1_700_000_000;
+        let targets = [Target::parse("example.com").unwrap().into_request()];
+        let cookies = [
+            cookie("example.com", "/", false, now + 1),
+            cookie("example.com", "/app", false, now + 1),
+            cookie("example.com", "/", false, 1),
+            cookie(".example.com", "/", true, 0),
+        ];
+
+        let selections = super::select(&targets, &cookies, false, now);
+        let summary: Vec<_> = selections[0]
+            .cookies
+            .iter()
+            .map(|c| (c.path.as_str(), c.is_secure, c.expiry))
+            .collect();
+        assert_eq!(summary, [("/", false, now + 1), ("/", true, 0)]);
+    }
+
+    #[test]
+    fn parses_scheme_only_at_start() {
+        let target = Target::parse("example.com/go?to=https://x").unwrap();
+        assert_eq!(target.host, "example.com");
+        assert_eq!(target.url.as_str(), "https://example.com/");
+
+        let error = Target::parse("http://[bad").unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "invalid url: http://[bad: invalid IPv6 address"
+        );
+    }
+
+    #[test]
+    fn bare_host_matches_parents_and_optionally_children() {
+        let target = Target::parse("accounts.example.com").unwrap();
+        assert!(target.matches(&cookie(".example.com", "/x", true, 0), false, 0));
+        assert!(target.matches(&cookie("accounts.example.com", "/", false, 0), false, 0));
+        assert!(!target.matches(&cookie("example.com", "/", false, 0), false, 0));
+        assert!(!target.matches(&cookie("a.accounts.example.com", "/", false, 0), false, 0));
+        assert!(target.matches(&cookie("a.accounts.example.com", "/", false, 0), true, 0));
+    }
+
+    #[test]
+    fn sorts_longer_paths_then_older_cookies_first() {
+        let mut cookies = vec![
+            MozCookie {
+                creation_time: 2,
+                ..cookie("example.com", "/", false, 0)
+            },
+            MozCookie {
+                creation_time: 1,
+                ..cookie("example.com", "/", false, 0)
+            },
+            MozCookie {
+                creation_time: 3,
+                ..cookie("example.com", "/app", false, 0)
+            },
+        ];
+        super::sort(&mut cookies);
+
+        let order: Vec<_> = cookies.iter().map(|c| c.creation_time).collect();
+        assert_eq!(order, [3, 1, 2]);
+    }
+    #[test]
+    fn candidates_include_parent_domains() {
+        assert_eq!(
+            super::candidate_hosts("accounts.youtube.com"),
+            [
+                "accounts.youtube.com",
+                ".accounts.youtube.com",
+                ".youtube.com",
+                ".com",
+            ]
+        );
+    }
+
+    #[test]
+    fn candidates_for_ip_address_are_exact() {
+        assert_eq!(super::candidate_hosts("127.0.0.1"), ["127.0.0.1"]);
+    }
+
+    #[test]
+    fn subdomain_pattern_escapes_wildcards() {
+        assert_eq!(super::subdomain_pattern("my_site.com"), "%.my\\_site.com");
+    }
+}
@@ -0,0 +1,625 @@
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::ArgEnum;
+use serde::Deserialize;
+
+#[derive(Clone, Debug)]
+pub struct Profile {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_default: bool,
+    pub browser: Browser,
+}
+
+impl Profile {
+    pub fn cookie_db(&self) -> PathBuf {
+        match self.browser.engine() {
+            Engine::Gecko | Engine::WebKit => self.path.join("cookies.sqlite"),
+            Engine::Chromium => chromium_cookie_db(&self.path),
+        }
+    }
+}
+
+/// The browser engine whose storage layout a profile follows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Engine {
+    Gecko,
+    Chromium,
+    /// WebKitGTK browsers, whose cookie jar borrows the `moz_cookies` table.
+    WebKit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ArgEnum)]
+pub enum Browser {
+    Firefox,
+    Librewolf,
+    Waterfox,
+    Floorp,
+    #[clap(name = "tor-browser")]
+    Tor,
+    Chrome,
+    Chromium,
+    Brave,
+    Vivaldi,
+    Edge,
+    Opera,
+    Epiphany,
+}
+
+impl Browser {
+    pub fn name(self) -> &'static str {
+        match self {
+            Browser::Firefox => "firefox",
+            Browser::Librewolf => "librewolf",
+            Browser::Waterfox => "waterfox",
+            Browser::Floorp => "floorp",
+            Browser::Tor => "tor-browser",
+            Browser::Chrome => "chrome",
+            Browser::Chromium => "chromium",
+            Browser::Brave => "brave",
+            Browser::Vivaldi => "vivaldi",
+            Browser::Edge => "edge",
+            Browser::Opera => "opera",
+            Browser::Epiphany => "epiphany",
+        }
+    }
+
+    pub fn engine(self) -> Engine {
+        match self {
+            Browser::Firefox
+            | Browser::Librewolf
+            | Browser::Waterfox
+            | Browser::Floorp
+            | Browser::Tor => Engine::Gecko,
+            Browser::Chrome
+            | Browser::Chromium
+            | Browser::Brave
+            | Browser::Vivaldi
+            | Browser::Edge
+            | Browser::Opera => Engine::Chromium,
+            Browser::Epiphany => Engine::WebKit,
+        }
+    }
+
+    /// Locations that may hold the browser's profiles, in the order they are searched.
+    pub fn roots(self) -> Vec<PathBuf> {
+        let home = |path: &str| dirs::home_dir().map(|home| home.join(path));
+        let data = |path: &str| dirs::data_dir().map(|data| data.join(path));
+        let config = |path: &str| dirs::config_dir().map(|config| config.join(path));
+        let roots = match self {
+            Browser::Firefox => return firefox_roots(),
+            Browser::Librewolf => vec![
+                home(".librewolf"),
+                home(".var/app/io.gitlab.librewolf-community/.librewolf"),
+            ],
+            Browser::Waterfox => vec![home(".waterfox")],
+            Browser::Floorp => vec![home(".floorp")],
+            // Tor Browser keeps its profile inside the bundle, wherever that was unpacked.
+            Browser::Tor => vec![
+                data("torbrowser/tbb/x86_64/tor-browser/Browser/TorBrowser/Data/Browser"),
+                home("tor-browser/Browser/TorBrowser/Data/Browser"),
+            ],
+            Browser::Chrome => vec![config("google-chrome")],
+            Browser::Chromium => vec![config("chromium")],
+            Browser::Brave => vec![config("BraveSoftware/Brave-Browser")],
+            Browser::Vivaldi => vec![config("vivaldi")],
+            Browser::Edge => vec![config("microsoft-edge")],
+            Browser::Opera => vec![config("opera")],
+            Browser::Epiphany => vec![
+                data("epiphany"),
+                home(".var/app/org.gnome.Epiphany/data/epiphany"),
+            ],
+        };
+        roots.into_iter().flatten().collect()
+    }
+
+    /// The Secret Service label of the password a Chromium-based browser encrypts cookies with.
+    /// Browsers that don't name their own entry share Chromium's or Chrome's.
+    pub fn safe_storage(self) -> &'static str {
+        match self {
+            Browser::Chrome | Browser::Vivaldi => "Chrome Safe Storage",
+            Browser::Brave => "Brave Safe Storage",
+            _ => "Chromium Safe Storage",
+        }
+    }
+}
+
+fn firefox_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(data) = dirs::data_dir() {
+        // Windows keeps profiles under %APPDATA%, macOS under Application Support.
+        roots.push(data.join("Mozilla").join("Firefox"));
+        roots.push(data.join("Firefox"));
+    }
+
+    // On Linux, Firefox eschews standard config locations, and the Flatpak and Snap packages
+    // each keep their own copy of the usual one.
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join(".mozilla/firefox"));
+        roots.push(home.join(".var/app/org.mozilla.firefox/.mozilla/firefox"));
+        roots.push(home.join("snap/firefox/common/.mozilla/firefox"));
+    }
+
+    roots
+}
+
+/// Chromium moved the cookie database into a `Network` subdirectory in version 96.
+fn chromium_cookie_db(profile: &Path) -> PathBuf {
+    let network = profile.join("Network").join("Cookies");
+    if network.is_file() {
+        network
+    } else {
+        profile.join("Cookies")
+    }
+}
+
+/// The browser picked, or every known browser in the order they are searched.
+pub fn browsers(browser: Option<Browser>) -> Vec<Browser> {
+    match browser {
+        Some(browser) => vec![browser],
+        None => Browser::value_variants().to_vec(),
+    }
+}
+
+/// Lists the profiles of `browser`, or of every known browser in turn.
+pub fn discover(browser: Option<Browser>) -> io::Result<Vec<Profile>> {
+    let mut profiles = Vec::new();
+    for browser in browsers(browser) {
+        let found = match browser.engine() {
+            Engine::Gecko => discover_gecko(browser)?,
+            Engine::Chromium => discover_chromium(browser)?,
+            Engine::WebKit => discover_webkit(browser),
+        };
+        profiles.extend(found);
+    }
+    Ok(profiles)
+}
+
+fn discover_gecko(browser: Browser) -> io::Result<Vec<Profile>> {
+    gecko_profiles(&browser.roots(), browser)
+}
+
+/// Reads the profiles listed in the `profiles.ini` of every root that has one.
+fn gecko_profiles(roots: &[PathBuf], browser: Browser) -> io::Result<Vec<Profile>> {
+    let mut profiles = Vec::new();
+    for root in roots {
+        let profiles_ini = match fs::read_to_string(root.join("profiles.ini")) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        let installs_ini = fs::read_to_string(root.join("installs.ini")).ok();
+        profiles.extend(parse(root, &profiles_ini, installs_ini.as_deref(), browser));
+    }
+
+    Ok(profiles)
+}
+
+/// WebKitGTK browsers keep a single cookie jar directly in each root.
+fn discover_webkit(browser: Browser) -> Vec<Profile> {
+    browser
+        .roots()
+        .into_iter()
+        .filter(|root| root.join("cookies.sqlite").is_file())
+        .enumerate()
+        .map(|(idx, path)| Profile {
+            name: browser.name().into(),
+            path,
+            is_default: idx == 0,
+            browser,
+        })
+        .collect()
+}
+
+/// Reads the profiles listed in `Local State`, falling back to every directory under the root
+/// that holds a cookie database.
+fn discover_chromium(browser: Browser) -> io::Result<Vec<Profile>> {
+    for root in browser.roots() {
+        match fs::read_to_string(root.join("Local State")) {
+            Ok(text) => {
+                let profiles = parse_local_state(&root, &text, browser);
+                if !profiles.is_empty() {
+                    return Ok(profiles);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e),
+        }
+
+        let profiles = scan_chromium(&root, browser)?;
+        if !profiles.is_empty() {
+            return Ok(profiles);
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+fn scan_chromium(root: &Path, browser: Browser) -> io::Result<Vec<Profile>> {
+    // Opera keeps its one profile in the root itself.
+    if chromium_cookie_db(root).is_file() {
+        return Ok(vec![Profile {
+            name: browser.name().into(),
+            path: root.into(),
+            is_default: true,
+            browser,
+        }]);
+    }
+
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut profiles = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !chromium_cookie_db(&path).is_file() {
+            continue;
+        }
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        profiles.push(Profile {
+            is_default: name == "Default",
+            name: name.into_owned(),
+            path,
+            browser,
+        });
+    }
+
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+#[derive(Deserialize)]
+struct LocalState {
+    #[serde(default)]
+    profile: ProfileState,
+}
+
+#[derive(Default, Deserialize)]
+struct ProfileState {
+    #[serde(default)]
+    info_cache: HashMap<String, ProfileInfo>,
+    last_used: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProfileInfo {
+    name: Option<String>,
+}
+
+/// Lists the profiles named in the `profile.info_cache` of a Chromium `Local State` file, with
+/// the last one used as the default.
+pub fn parse_local_state(root: &Path, local_state: &str, browser: Browser) -> Vec<Profile> {
+    let state = match serde_json::from_str::<LocalState>(local_state) {
+        Ok(state) => state.profile,
+        Err(_) => return Vec::new(),
+    };
+
+    let last_used = state.last_used.as_deref().unwrap_or("Default");
+    let mut profiles: Vec<_> = state
+        .info_cache
+        .into_iter()
+        .map(|(dir, info)| Profile {
+            is_default: dir == last_used,
+            path: root.join(&dir),
+            name: info.name.unwrap_or(dir),
+            browser,
+        })
+        .collect();
+
+    profiles.sort_by(|a, b| a.path.cmp(&b.path));
+    profiles
+}
+
+/// Finds a profile by name, or by the name of its directory.
+pub fn find<'a>(profiles: &'a [Profile], key: &str) -> Option<&'a Profile> {
+    profiles
+        .iter()
+        .find(|profile| profile.name == key)
+        .or_else(|| {
+            profiles
+                .iter()
+                .find(|profile| profile.path.file_name() == Some(OsStr::new(key)))
+        })
+}
+
+/// Treats `path` as a profile directory or a path to a cookie database, if it holds one.
+pub fn cookie_db_at(path: &Path) -> Option<PathBuf> {
+    let db = if path.join("cookies.sqlite").is_file() {
+        path.join("cookies.sqlite")
+    } else if path.is_dir() {
+        chromium_cookie_db(path)
+    } else {
+        path.into()
+    };
+    db.is_file().then_some(db)
+}
+
+pub fn parse(
+    root: &Path,
+    profiles_ini: &str,
+    installs_ini: Option<&str>,
+    browser: Browser,
+) -> Vec<Profile> {
+    let profiles_ini = parse_ini(profiles_ini);
+    let installs_ini = installs_ini.map(parse_ini).unwrap_or_default();
+
+    // Install sections name the profile a given Firefox install actually launches with. They
+    // live in installs.ini, with older copies mirrored into profiles.ini.
+    let installs = installs_ini.iter().chain(
+        profiles_ini
+            .iter()
+            .filter(|section| section.name.starts_with("Install")),
+    );
+
+    let mut install_default = None;
+    for install in installs {
+        if let Some(default) = install.get("Default") {
+            if install.get("Locked") == Some("1") {
+                install_default = Some(default);
+                break;
+            }
+            install_default.get_or_insert(default);
+        }
+    }
+
+    let mut raw_paths = Vec::new();
+    let mut marked_default = None;
+    let mut profiles = Vec::new();
+    for section in profiles_ini
+        .iter()
+        .filter(|section| section.name.starts_with("Profile"))
+    {
+        let raw_path = match section.get("Path") {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let path = if section.get("IsRelative") == Some("0") {
+            PathBuf::from(raw_path)
+        } else {
+            raw_path
+                .split('/')
+                .fold(root.to_owned(), |path, part| path.join(part))
+        };
+
+        if section.get("Default") == Some("1") {
+            marked_default.get_or_insert(profiles.len());
+        }
+
+        raw_paths.push(raw_path);
+        profiles.push(Profile {
+            name: section.get("Name").unwrap_or(raw_path).into(),
+            path,
+            is_default: false,
+            browser,
+        });
+    }
+
+    let default = install_default
+        .and_then(|default| raw_paths.iter().position(|&path| path == default))
+        .or(marked_default);
+
+    if let Some(idx) = default {
+        profiles[idx].is_default = true;
+    }
+
+    profiles
+}
+
+struct Section {
+    name: String,
+    entries: Vec<(String, String)>,
+}
+
+impl Section {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn parse_ini(text: &str) -> Vec<Section> {
+    let mut sections = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            sections.push(Section {
+                name: name.into(),
+                entries: Vec::new(),
+            });
+        } else if let (Some(section), Some((key, value))) =
+            (sections.last_mut(), line.split_once('='))
+        {
+            section
+                .entries
+                .push((key.trim().into(), value.trim().into()));
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Browser;
+
+    static PROFILES_INI: &str = "\
+[Install308046B0AF4A39CB]
+Default=Profiles/stale.default
+Locked=1
+
+[Profile2]
+Name=work
+IsRelative=0
+Path=/srv/firefox/work
+
+[Profile1]
+Name=default
+IsRelative=1
+Path=Profiles/abcd.default
+Default=1
+
+[Profile0]
+Name=default-release
+IsRelative=1
+Path=Profiles/efgh.default-release
+
+[General]
+StartWithLastProfile=1
+Version=2
+";
+
+    static INSTALLS_INI: &str = "\
+[308046B0AF4A39CB]
+Default=Profiles/efgh.default-release
+Locked=1
+";
+
+    #[test]
+    fn resolves_relative_and_absolute_paths() {
+        let root = Path::new("/home/user/.mozilla/firefox");
+        let profiles = super::parse(root, PROFILES_INI, None, Browser::Firefox);
+        let paths: Vec<_> = profiles.iter().map(|p| p.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("/srv/firefox/work"),
+                &root.join("Profiles").join("abcd.default"),
+                &root.join("Profiles").join("efgh.default-release"),
+            ]
+        );
+    }
+
+    #[test]
+    fn prefers_install_locked_default() {
+        let root = Path::new("/home/user/.mozilla/firefox");
+        let profiles = super::parse(root, PROFILES_INI, Some(INSTALLS_INI), Browser::Firefox);
+        let defaults: Vec<_> = profiles
+            .iter()
+            .filter(|p| p.is_default)
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(defaults, ["default-release"]);
+    }
+
+    #[test]
+    fn falls_back_to_profile_default() {
+        let root = Path::new("/home/user/.mozilla/firefox");
+        let profiles = super::parse(root, PROFILES_INI, None, Browser::Firefox);
+        let defaults: Vec<_> = profiles
+            .iter()
+            .filter(|p| p.is_default)
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(defaults, ["default"]);
+    }
+
+    #[test]
+    fn reads_chromium_local_state() {
+        let root = Path::new("/home/user/.config/BraveSoftware/Brave-Browser");
+        let local_state = r#"{
+            "browser": { "enabled_labs_experiments": [] },
+            "profile": {
+                "info_cache": {
+                    "Profile 1": { "name": "Work", "is_using_default_name": false },
+                    "Default": { "name": "Person 1" }
+                },
+                "last_used": "Profile 1"
+            }
+        }"#;
+
+        let profiles = super::parse_local_state(root, local_state, Browser::Brave);
+        let summary: Vec<_> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.path.clone(), p.is_default))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Person 1", root.join("Default"), false),
+                ("Work", root.join("Profile 1"), true),
+            ]
+        );
+        assert_eq!(
+            profiles[1].cookie_db(),
+            root.join("Profile 1").join("Cookies")
+        );
+    }
+
+    #[test]
+    fn reads_every_gecko_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let native = dir.path().join(".mozilla/firefox");
+        let snap = dir.path().join("snap/firefox/common/.mozilla/firefox");
+        let missing = dir
+            .path()
+            .join(".var/app/org.mozilla.firefox/.mozilla/firefox");
+        for (root, name) in [(&native, "native"), (&snap, "snap")] {
+            std::fs::create_dir_all(root).unwrap();
+            let profiles_ini = format!(
+                "[Profile0]\nName={}\nIsRelative=1\nPath=p.default\nDefault=1\n",
+                name
+            );
+            std::fs::write(root.join("profiles.ini"), profiles_ini).unwrap();
+        }
+
+        let profiles =
+            super::gecko_profiles(&[native.clone(), missing, snap.clone()], Browser::Firefox)
+                .unwrap();
+        let summary: Vec<_> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.path.clone(), p.is_default))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("native", native.join("p.default"), true),
+                ("snap", snap.join("p.default"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_cookie_db_only_where_one_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let firefox = dir.path().join("firefox");
+        let chromium = dir.path().join("chromium");
+        let empty = dir.path().join("empty");
+        for path in [&firefox, &chromium.join("Network"), &empty] {
+            std::fs::create_dir_all(path).unwrap();
+        }
+        std::fs::write(firefox.join("cookies.sqlite"), "").unwrap();
+        std::fs::write(chromium.join("Network/Cookies"), "").unwrap();
+
+        assert_eq!(
+            super::cookie_db_at(&firefox),
+            Some(firefox.join("cookies.sqlite"))
+        );
+        assert_eq!(
+            super::cookie_db_at(&chromium),
+            Some(chromium.join("Network/Cookies"))
+        );
+        assert_eq!(
+            super::cookie_db_at(&firefox.join("cookies.sqlite")),
+            Some(firefox.join("cookies.sqlite"))
+        );
+        assert_eq!(super::cookie_db_at(&empty), None);
+        assert_eq!(super::cookie_db_at(&dir.path().join("missing")), None);
+    }
+}
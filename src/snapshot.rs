@@ -0,0 +1,126 @@
+use std::{
+    fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use rusqlite::Connection;
+use tempfile::TempDir;
+
+/// A private copy of a cookie database.
+///
+/// Firefox keeps `cookies.sqlite` open (and locked) while it runs, with recent writes parked in
+/// the write-ahead log. Copying the database alongside its `-wal` and `-shm` files lets us read
+/// everything the browser has without contending for the lock or touching the profile.
+pub struct Snapshot {
+    // Declared first so the connection closes before the directory is removed.
+    connection: Connection,
+    _dir: TempDir,
+}
+
+impl Snapshot {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        Self::copy(path).with_context(|| format!("cannot read cookie database {}", path.display()))
+    }
+
+    fn copy(path: &Path) -> anyhow::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let file_name = path
+            .file_name()
+            .unwrap_or_else(|| "cookies.sqlite".as_ref());
+        let target = dir.path().join(file_name);
+
+        fs::copy(path, &target)?;
+        for suffix in ["-wal", "-shm"] {
+            match fs::copy(with_suffix(path, suffix), with_suffix(&target, suffix)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
+        }
+
+        Ok(Snapshot {
+            connection: Connection::open(&target)?,
+            _dir: dir,
+        })
+    }
+}
+
+impl Deref for Snapshot {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.connection
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, time::SystemTime};
+
+    use rusqlite::Connection;
+
+    fn stat(path: &Path) -> (u64, SystemTime) {
+        let meta = fs::metadata(path).unwrap();
+        (meta.len(), meta.modified().unwrap())
+    }
+
+    #[test]
+    fn reads_rows_left_in_write_ahead_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cookies.sqlite");
+
+        // Stands in for the running browser, holding its writes in the log.
+        let browser = Connection::open(&path).unwrap();
+        let mode: String = browser
+            .query_row("pragma journal_mode = wal", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+        let pages: i64 = browser
+            .query_row("pragma wal_autocheckpoint = 0", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(pages, 0);
+        browser
+            .execute_batch(
+                "create table moz_cookies (name text);
+                insert into moz_cookies values ('a'), ('b');",
+            )
+            .unwrap();
+
+        let files: Vec<_> = ["", "-wal", "-shm"]
+            .into_iter()
+            .map(|suffix| super::with_suffix(&path, suffix))
+            .collect();
+        assert!(fs::metadata(&files[1]).unwrap().len() > 0);
+        let before: Vec<_> = files.iter().map(|file| stat(file)).collect();
+
+        let snapshot = super::Snapshot::open(&path).unwrap();
+        let count: i64 = snapshot
+            .query_row("select count(*) from moz_cookies", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+        drop(snapshot);
+
+        let after: Vec<_> = files.iter().map(|file| stat(file)).collect();
+        assert_eq!(before, after);
+        drop(browser);
+    }
+
+    #[test]
+    fn names_missing_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cookies.sqlite");
+
+        let error = super::Snapshot::open(&path).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            format!("cannot read cookie database {}", path.display())
+        );
+    }
+}